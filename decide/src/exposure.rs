@@ -0,0 +1,132 @@
+use std::fs::File;
+use std::sync::Arc;
+
+use arrow::array::{Int64Array, StringArray};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+
+/// A single "this unit saw this variant" event, buffered on `PyDecider` and
+/// flushed to Parquet so exposure logging survives the FFI boundary without
+/// a round trip through Python for every `choose` call.
+#[derive(Clone, Debug)]
+pub struct ExposureRecord {
+    pub timestamp: i64,
+    pub feature_name: String,
+    pub variant_name: String,
+    pub bucketing_value: String,
+    pub experiment_id: i64,
+}
+
+fn schema() -> Schema {
+    Schema::new(vec![
+        Field::new("timestamp", DataType::Int64, false),
+        Field::new("feature_name", DataType::Utf8, false),
+        Field::new("variant_name", DataType::Utf8, false),
+        Field::new("bucketing_value", DataType::Utf8, false),
+        Field::new("experiment_id", DataType::Int64, false),
+    ])
+}
+
+fn write_properties() -> WriterProperties {
+    // Dictionary encoding is on by default for every column, so no per-column
+    // encoding is set here. `set_column_encoding` only chooses the encoding the
+    // writer falls back to when a dictionary grows too large, and it rejects
+    // the dictionary encodings themselves.
+    WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .build()
+}
+
+/// Writes buffered exposures to `path` as a single columnar Parquet file,
+/// one column chunk per field, matching the Arrow/Parquet `ArrowWriter`
+/// pattern rather than a row-oriented dump.
+pub fn write_parquet(records: &[ExposureRecord], path: &str) -> Result<(), parquet::errors::ParquetError> {
+    let schema = Arc::new(schema());
+
+    let timestamps = Int64Array::from_iter_values(records.iter().map(|r| r.timestamp));
+    let feature_names = StringArray::from_iter_values(records.iter().map(|r| r.feature_name.as_str()));
+    let variant_names = StringArray::from_iter_values(records.iter().map(|r| r.variant_name.as_str()));
+    let bucketing_values = StringArray::from_iter_values(records.iter().map(|r| r.bucketing_value.as_str()));
+    let experiment_ids = Int64Array::from_iter_values(records.iter().map(|r| r.experiment_id));
+
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(timestamps),
+            Arc::new(feature_names),
+            Arc::new(variant_names),
+            Arc::new(bucketing_values),
+            Arc::new(experiment_ids),
+        ],
+    )?;
+
+    let file = File::create(path)?;
+    let mut writer = ArrowWriter::try_new(file, schema, Some(write_properties()))?;
+    writer.write(&batch)?;
+    writer.close()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::Array;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    #[test]
+    fn write_parquet_round_trips_records() {
+        let dir = std::env::temp_dir();
+        let path = dir.join(format!("exposure-test-{:?}.parquet", std::thread::current().id()));
+        let path = path.to_str().unwrap();
+
+        let records = vec![
+            ExposureRecord {
+                timestamp: 1,
+                feature_name: "my_feature".to_string(),
+                variant_name: "treatment".to_string(),
+                bucketing_value: "t2_abc".to_string(),
+                experiment_id: 42,
+            },
+            ExposureRecord {
+                timestamp: 2,
+                feature_name: "my_feature".to_string(),
+                variant_name: "control".to_string(),
+                bucketing_value: "t2_def".to_string(),
+                experiment_id: 42,
+            },
+        ];
+
+        write_parquet(&records, path).expect("write_parquet should not fail");
+
+        let file = File::open(path).unwrap();
+        let mut reader = ParquetRecordBatchReaderBuilder::try_new(file)
+            .unwrap()
+            .build()
+            .unwrap();
+        let batch = reader.next().unwrap().unwrap();
+
+        assert_eq!(batch.num_rows(), 2);
+
+        let feature_names = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(feature_names.value(0), "my_feature");
+        assert_eq!(feature_names.value(1), "my_feature");
+
+        let variant_names = batch
+            .column(2)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(variant_names.value(0), "treatment");
+        assert_eq!(variant_names.value(1), "control");
+
+        std::fs::remove_file(path).ok();
+    }
+}
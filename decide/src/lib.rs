@@ -1,13 +1,90 @@
+mod exposure;
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
 use pyo3::prelude::*;
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::types::{PyDict, PyList};
+use rust_decimal::Decimal;
 use decider::init_decider;
 use decider::Decider;
 use decider::Context;
 use decider::Decision;
+use decider::DeciderError as RustDeciderError;
+
+use exposure::ExposureRecord;
+
+/// Converts a `rust_decimal::Decimal` into a Python `decimal.Decimal`, going
+/// through its exact string representation so monetary values like `1.07`
+/// don't pick up binary float rounding on the way across the FFI boundary.
+fn decimal_to_py(py: Python, d: Decimal) -> PyResult<PyObject> {
+    let decimal_cls = py.import("decimal")?.getattr("Decimal")?;
+    Ok(decimal_cls.call1((d.to_string(),))?.into())
+}
+
+/// Parses an `f64` default into the shortest `Decimal` that round-trips to
+/// the same value (e.g. `1.07`, not `1.0700000000000000622...`), so a default
+/// written as `1.07` in Python doesn't pick up binary float rounding.
+fn decimal_from_f64(default: f64) -> Option<Decimal> {
+    Decimal::from_f64(default)
+}
+
+#[cfg(test)]
+mod decimal_tests {
+    use super::*;
+
+    #[test]
+    fn decimal_from_f64_round_trips_shortest_representation() {
+        let d = decimal_from_f64(1.07).unwrap();
+        assert_eq!(d.to_string(), "1.07");
+    }
+}
+
 
+create_exception!(rust, DeciderError, PyException);
+create_exception!(rust, FeatureNotFoundError, DeciderError);
+create_exception!(rust, ConfigParseError, DeciderError);
+create_exception!(rust, InvalidContextError, DeciderError);
+create_exception!(rust, BucketingError, DeciderError);
+
+fn to_py_err(e: RustDeciderError) -> PyErr {
+    match e {
+        RustDeciderError::FeatureNotFound(msg) => FeatureNotFoundError::new_err(msg),
+        RustDeciderError::ConfigParse(msg) => ConfigParseError::new_err(msg),
+        RustDeciderError::InvalidContext(msg) => InvalidContextError::new_err(msg),
+        RustDeciderError::Bucketing(msg) => BucketingError::new_err(msg),
+        other => DeciderError::new_err(other.to_string()),
+    }
+}
 
 #[pyclass]
 pub struct PyDecider {
     inner: Decider,
+    exposures: Mutex<Vec<ExposureRecord>>,
+}
+
+fn current_timestamp_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+fn record_exposure(decider: &PyDecider, feature_name: &str, decision: &Option<Decision>) {
+    if let Some(d) = decision {
+        let record = ExposureRecord {
+            timestamp: current_timestamp_millis(),
+            feature_name: feature_name.to_string(),
+            variant_name: d.variant_name.clone(),
+            bucketing_value: d.bucketing_value.clone(),
+            experiment_id: d.experiment_id,
+        };
+
+        decider.exposures.lock().unwrap().push(record);
+    }
 }
 
 #[pyclass]
@@ -15,44 +92,275 @@ pub struct PyContext {
     inner: Context,
 }
 
+#[pymethods]
+impl PyContext {
+    #[new]
+    #[args(kwargs = "**")]
+    pub fn new(kwargs: Option<&PyDict>) -> PyResult<Self> {
+        let mut user_id: Option<String> = None;
+        let mut device_id: Option<String> = None;
+        let mut locale: Option<String> = None;
+        let mut country_code: Option<String> = None;
+        let mut other: HashMap<String, String> = HashMap::new();
+
+        for (merged_key, merged_value) in kwargs.into_iter().flatten() {
+            let key: String = merged_key.extract()?;
+            match key.as_str() {
+                "user_id" => user_id = Some(merged_value.extract()?),
+                "device_id" => device_id = Some(merged_value.extract()?),
+                "locale" => locale = Some(merged_value.extract()?),
+                "country_code" => country_code = Some(merged_value.extract()?),
+                _ => {
+                    other.insert(key, merged_value.extract()?);
+                }
+            }
+        }
+
+        Ok(PyContext {
+            inner: Context {
+                user_id,
+                device_id,
+                locale,
+                country_code,
+                other,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod context_tests {
+    use super::*;
+
+    #[test]
+    fn new_routes_known_fields_and_falls_through_to_other() {
+        Python::with_gil(|py| {
+            let kwargs = PyDict::new(py);
+            kwargs.set_item("user_id", "t2_abc").unwrap();
+            kwargs.set_item("device_id", "dev-1").unwrap();
+            kwargs.set_item("locale", "en-US").unwrap();
+            kwargs.set_item("country_code", "US").unwrap();
+            kwargs.set_item("experiment_group", "holdout").unwrap();
+
+            let ctx = PyContext::new(Some(kwargs)).unwrap();
+
+            assert_eq!(ctx.inner.user_id.as_deref(), Some("t2_abc"));
+            assert_eq!(ctx.inner.device_id.as_deref(), Some("dev-1"));
+            assert_eq!(ctx.inner.locale.as_deref(), Some("en-US"));
+            assert_eq!(ctx.inner.country_code.as_deref(), Some("US"));
+            assert_eq!(
+                ctx.inner.other.get("experiment_group").map(String::as_str),
+                Some("holdout")
+            );
+            assert_eq!(ctx.inner.other.len(), 1);
+        });
+    }
+
+    #[test]
+    fn new_with_no_kwargs_leaves_everything_empty() {
+        Python::with_gil(|py| {
+            let _ = py;
+            let ctx = PyContext::new(None).unwrap();
+
+            assert!(ctx.inner.user_id.is_none());
+            assert!(ctx.inner.other.is_empty());
+        });
+    }
+}
+
 #[pyclass]
 pub struct PyDecision {
     inner: Option<Decision>,
 }
 
+#[pymethods]
+impl PyDecision {
+    pub fn is_none(&self) -> bool {
+        self.inner.is_none()
+    }
+
+    #[getter]
+    pub fn variant_name(&self) -> Option<String> {
+        self.inner.as_ref().map(|d| d.variant_name.clone())
+    }
+
+    #[getter]
+    pub fn experiment_id(&self) -> Option<i64> {
+        self.inner.as_ref().map(|d| d.experiment_id)
+    }
+
+    #[getter]
+    pub fn experiment_name(&self) -> Option<String> {
+        self.inner.as_ref().map(|d| d.experiment_name.clone())
+    }
+
+    #[getter]
+    pub fn bucketing_value(&self) -> Option<String> {
+        self.inner.as_ref().map(|d| d.bucketing_value.clone())
+    }
+
+    #[getter]
+    pub fn is_bucketed(&self) -> Option<bool> {
+        self.inner.as_ref().map(|d| d.is_bucketed)
+    }
+
+    #[getter]
+    pub fn override_reason(&self) -> Option<String> {
+        self.inner.as_ref().and_then(|d| d.override_reason.clone())
+    }
+
+    pub fn __repr__(&self) -> String {
+        match &self.inner {
+            None => "PyDecision(None)".to_string(),
+            Some(d) => format!(
+                "PyDecision(experiment_name={:?}, variant_name={:?}, is_bucketed={}, override_reason={:?})",
+                d.experiment_name, d.variant_name, d.is_bucketed, d.override_reason,
+            ),
+        }
+    }
+
+    pub fn __bool__(&self) -> bool {
+        self.inner.is_some()
+    }
+}
+
 #[pymethods]
 impl PyDecider {
     pub fn printer(&self) {
         println!("yooo");
     }
 
-    pub fn choose(&self, feature_name: String, ctx: &PyContext) -> Option<PyDecision> {
-        let result = self.inner.choose(feature_name.to_string(), &ctx.inner);
+    pub fn choose(&self, feature_name: String, ctx: &PyContext) -> PyResult<PyDecision> {
+        let res = self.inner
+            .choose(feature_name.to_string(), &ctx.inner)
+            .map_err(to_py_err)?;
+
+        record_exposure(self, &feature_name, &res);
+
+        Ok(PyDecision { inner: res })
+    }
+
+    #[args(filter = "None")]
+    pub fn choose_all(&self, py: Python, ctx: &PyContext, filter: Option<Vec<String>>) -> PyResult<PyObject> {
+        let feature_names = filter.unwrap_or_else(|| self.inner.feature_names());
+
+        let result = PyDict::new(py);
+        for feature_name in feature_names {
+            let decision = self.inner
+                .choose(feature_name.clone(), &ctx.inner)
+                .map_err(to_py_err)?;
+
+            record_exposure(self, &feature_name, &decision);
+
+            result.set_item(feature_name, PyDecision { inner: decision })?;
+        }
+
+        Ok(result.into())
+    }
+
+    /// Writes every buffered exposure to `path` as a single Parquet file and
+    /// clears the buffer. Call this on a schedule (or at process shutdown) so
+    /// exposures don't accumulate unbounded in memory.
+    pub fn flush_exposures(&self, path: String) -> PyResult<()> {
+        let mut buffer = self.exposures.lock().unwrap();
+
+        exposure::write_parquet(&buffer, &path)
+            .map_err(|e| DeciderError::new_err(e.to_string()))?;
+
+        buffer.clear();
+        Ok(())
+    }
+
+    /// Drains the buffered exposures and returns them as a list of dicts, for
+    /// callers who ship exposure logging over their own pipeline instead of
+    /// `flush_exposures`.
+    pub fn drain_exposures(&self, py: Python) -> PyResult<PyObject> {
+        let records: Vec<ExposureRecord> = self.exposures.lock().unwrap().drain(..).collect();
 
-        return match result {
-            Ok(res) => Some(PyDecision{inner : res}),
-            Err(_e) => None, 
+        let result = PyList::empty(py);
+        for record in records {
+            let item = PyDict::new(py);
+            item.set_item("timestamp", record.timestamp)?;
+            item.set_item("feature_name", record.feature_name)?;
+            item.set_item("variant_name", record.variant_name)?;
+            item.set_item("bucketing_value", record.bucketing_value)?;
+            item.set_item("experiment_id", record.experiment_id)?;
+            result.append(item)?;
         }
+
+        Ok(result.into())
+    }
+
+    pub fn get_bool(&self, config_name: String, ctx: &PyContext, default: bool) -> PyResult<bool> {
+        self.inner
+            .get_bool(config_name.to_string(), &ctx.inner, default)
+            .map_err(to_py_err)
+    }
+
+    pub fn get_int(&self, config_name: String, ctx: &PyContext, default: i64) -> PyResult<i64> {
+        self.inner
+            .get_int(config_name.to_string(), &ctx.inner, default)
+            .map_err(to_py_err)
+    }
+
+    pub fn get_string(&self, config_name: String, ctx: &PyContext, default: String) -> PyResult<String> {
+        self.inner
+            .get_string(config_name.to_string(), &ctx.inner, default)
+            .map_err(to_py_err)
+    }
+
+    pub fn get_float(&self, py: Python, config_name: String, ctx: &PyContext, default: f64) -> PyResult<PyObject> {
+        let default_decimal = decimal_from_f64(default)
+            .ok_or_else(|| DeciderError::new_err("default value is not a finite float"))?;
+
+        let value = self.inner
+            .get_float(config_name.to_string(), &ctx.inner, default_decimal)
+            .map_err(to_py_err)?;
+
+        decimal_to_py(py, value)
+    }
+
+    pub fn get_map(&self, py: Python, config_name: String, ctx: &PyContext, default: &PyDict) -> PyResult<PyObject> {
+        let default_map: HashMap<String, String> = default.extract()?;
+
+        let value = self.inner
+            .get_map(config_name.to_string(), &ctx.inner, default_map)
+            .map_err(to_py_err)?;
+
+        let result = PyDict::new(py);
+        for (k, v) in value.iter() {
+            result.set_item(k, v)?;
+        }
+
+        Ok(result.into())
     }
 }
 
 #[pyfunction]
-pub fn init(decisionmakers: String, filename: String) -> Option<PyDecider> {
-    let d = init_decider(
+pub fn init(decisionmakers: String, filename: String) -> PyResult<PyDecider> {
+    let dec = init_decider(
         decisionmakers.to_string(),
         filename.to_string(),
-    );
-    
-    return match d {
-        Ok(dec) => Some(PyDecider{inner : dec}),
-        Err(_e) => None,
-    }
+    ).map_err(to_py_err)?;
+
+    Ok(PyDecider {
+        inner: dec,
+        exposures: Mutex::new(Vec::new()),
+    })
 }
 
 #[pymodule]
-fn rust(_py: Python, m: &PyModule) -> PyResult<()> {
+fn rust(py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<PyDecider>()?;
-    m.add_function(wrap_pyfunction!(init, m)?)?;    
+    m.add_class::<PyContext>()?;
+    m.add_class::<PyDecision>()?;
+    m.add_function(wrap_pyfunction!(init, m)?)?;
+
+    m.add("DeciderError", py.get_type::<DeciderError>())?;
+    m.add("FeatureNotFoundError", py.get_type::<FeatureNotFoundError>())?;
+    m.add("ConfigParseError", py.get_type::<ConfigParseError>())?;
+    m.add("InvalidContextError", py.get_type::<InvalidContextError>())?;
+    m.add("BucketingError", py.get_type::<BucketingError>())?;
 
     Ok(())
 }